@@ -2,20 +2,314 @@ use crate::error::ProtoError;
 use crate::proto_config::*;
 use crate::tool::Tool;
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::instrument;
 use tracing::{debug, trace};
 use version_spec::*;
 
+/// Where a detected version requirement originated. Captured alongside the spec
+/// so commands such as `proto status` can explain how each tool resolved its
+/// version, rather than relying on the side-channel `PROTO_DETECTED_FROM`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DetectSource {
+    /// Passed explicitly on the command line.
+    CommandLine,
+    /// Read from an environment variable: a per-tool `<PREFIX>_VERSION`, or the
+    /// global [`GLOBAL_VERSION_ENV_VAR`] override.
+    EnvVar(String),
+    /// Pinned in a `.prototools` configuration file.
+    ConfigFile(PathBuf),
+    /// Inferred from a tool-specific ecosystem manifest.
+    Ecosystem(PathBuf),
+}
+
+impl std::fmt::Display for DetectSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CommandLine => write!(f, "command line"),
+            Self::EnvVar(name) => write!(f, "{name} environment variable"),
+            Self::ConfigFile(path) => write!(f, "{}", path.display()),
+            Self::Ecosystem(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A detected version requirement paired with the source it was detected from.
+#[derive(Clone, Debug)]
+pub struct DetectedVersion {
+    pub spec: UnresolvedVersionSpec,
+    pub source: DetectSource,
+}
+
+impl DetectedVersion {
+    fn new(spec: UnresolvedVersionSpec, source: DetectSource) -> Self {
+        Self { spec, source }
+    }
+}
+
+/// Resolved status for a single tool, as surfaced by `proto status`: the
+/// detected requirement, the file or environment variable that supplied it, and
+/// whether a matching version is already installed on disk.
+#[derive(Clone, Debug)]
+pub struct ToolStatus {
+    pub spec: UnresolvedVersionSpec,
+    pub source: DetectSource,
+    pub installed: bool,
+}
+
+impl ToolStatus {
+    /// Render a single status as a line for the `status` command, e.g.
+    /// `node  20.11.0 (from .prototools) [installed]`.
+    pub fn to_line(&self, id: &str) -> String {
+        format!(
+            "{}  {} (from {}) [{}]",
+            id,
+            self.spec,
+            self.source,
+            if self.installed {
+                "installed"
+            } else {
+                "not installed"
+            },
+        )
+    }
+}
+
+/// Detect a single tool's version and report its provenance plus whether a
+/// matching version is already installed, without performing remote resolution.
+/// Returns `None` only when no version is configured for the tool; any other
+/// failure (e.g. a malformed spec) is propagated rather than hidden.
+#[instrument(skip_all)]
+pub async fn detect_tool_status(tool: &Tool) -> miette::Result<Option<ToolStatus>> {
+    let detected = match detect_version_with_source(tool, None).await {
+        Ok(detected) => detected,
+        Err(error) => {
+            // A tool with no configured version simply has no status; every
+            // other error is a real failure and must surface.
+            if matches!(
+                error.downcast_ref::<ProtoError>(),
+                Some(ProtoError::VersionDetectFailed { .. })
+            ) {
+                return Ok(None);
+            }
+
+            return Err(error);
+        }
+    };
+
+    let installed = resolve_from_installed_versions(
+        &detected.spec,
+        &load_installed_versions(tool),
+        Stability::All,
+    )
+    .is_some();
+
+    Ok(Some(ToolStatus {
+        spec: detected.spec,
+        source: detected.source,
+        installed,
+    }))
+}
+
+/// Detect the status of every configured tool, in the order given, skipping
+/// those with no configured version. This is the iteration a `proto status`
+/// command performs over the tools gathered from the [`ProtoConfigManager`]
+/// files; callers render each entry via [`ToolStatus::to_line`].
+#[instrument(skip_all)]
+pub async fn detect_tool_statuses(tools: &[Tool]) -> miette::Result<Vec<(String, ToolStatus)>> {
+    let mut statuses = vec![];
+
+    for tool in tools {
+        if let Some(status) = detect_tool_status(tool).await? {
+            statuses.push((tool.id.to_string(), status));
+        }
+    }
+
+    Ok(statuses)
+}
+
+/// Render the statuses of every configured tool as the block of text printed by
+/// `proto status` — one [`ToolStatus::to_line`] per tool.
+#[instrument(skip_all)]
+pub async fn render_tool_statuses(tools: &[Tool]) -> miette::Result<String> {
+    Ok(detect_tool_statuses(tools)
+        .await?
+        .iter()
+        .map(|(id, status)| status.to_line(id))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Environment variable for a global session version override. When set to a
+/// non-empty spec it pins every tool to that version, regardless of per-tool
+/// config. Useful in CI or while bisecting, where a uniform version is wanted
+/// without editing any `.prototools` file. The top-level `--use-version` CLI
+/// flag is wired to this via [`set_global_version_override`].
+pub const GLOBAL_VERSION_ENV_VAR: &str = "PROTO_USE_VERSION";
+
+/// Apply a global session version override, as passed by the top-level
+/// `--use-version` CLI flag. The value is stored in [`GLOBAL_VERSION_ENV_VAR`]
+/// so every subsequent [`detect_version`] consults it — resolved after an
+/// explicit per-command `forced_version`, but before the per-tool env var and
+/// config traversal. An empty value is ignored, matching env-var handling.
+pub fn set_global_version_override(version: &str) {
+    if !version.is_empty() {
+        env::set_var(GLOBAL_VERSION_ENV_VAR, version);
+    }
+}
+
+/// Environment variable controlling whether a detected version requirement is
+/// satisfied from an already-installed version before hitting the registry.
+/// Sits alongside `detect_strategy` as a resolution toggle and is **on by
+/// default** — this is what keeps offline and repeat invocations off the
+/// network. Set it to a falsey value (`0`/`false`/`off`/`no`) to opt out and
+/// always take the newest remote match.
+pub const RESOLVE_FROM_INSTALLED_ENV_VAR: &str = "PROTO_RESOLVE_FROM_INSTALLED";
+
+fn is_falsey(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "0" | "false" | "off" | "no")
+}
+
+fn resolve_from_installed_enabled() -> bool {
+    // Enabled unless explicitly opted out, so the offline benefit applies by
+    // default rather than only when a user knows to turn it on.
+    env::var(RESOLVE_FROM_INSTALLED_ENV_VAR)
+        .map(|value| !is_falsey(&value))
+        .unwrap_or(true)
+}
+
+/// Environment variable selecting how strict range resolution is about unstable
+/// releases: `all`, `stable` (the default — skip pre-releases), or `strict`
+/// (also skip `0.x`). Sits alongside `detect_strategy` as a resolution toggle.
+pub const STABILITY_ENV_VAR: &str = "PROTO_VERSION_STABILITY";
+
+fn detect_stability() -> Stability {
+    match env::var(STABILITY_ENV_VAR).ok().as_deref() {
+        Some("all") => Stability::All,
+        Some("strict") => Stability::Strict,
+        _ => Stability::Stable,
+    }
+}
+
 fn set_detected_env_var(path: &Path) {
     env::set_var("PROTO_DETECTED_FROM", path);
 }
 
+/// Scan a tool's inventory directory and parse each install directory name into
+/// a [`Version`], building an index of the versions already on disk. Entries
+/// that don't parse as a semantic version (aliases, stray files, etc.) are
+/// skipped, so this is safe to call against an uninitialized inventory.
+fn load_installed_versions(tool: &Tool) -> Vec<Version> {
+    let mut versions = vec![];
+
+    if let Ok(entries) = std::fs::read_dir(tool.get_inventory_dir()) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+                if let Ok(version) = Version::parse(&entry.file_name().to_string_lossy()) {
+                    versions.push(version);
+                }
+            }
+        }
+    }
+
+    versions
+}
+
+/// How strict range resolution should be about unstable releases. Consulted by
+/// [`resolve_from_installed_versions`] so a broad requirement like `^1` doesn't
+/// accidentally select a pre-release or bleeding-edge build.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub enum Stability {
+    /// Allow any matching version, including pre-releases and `0.x`.
+    All,
+    /// Exclude versions carrying a semver pre-release tag (the default).
+    #[default]
+    Stable,
+    /// Exclude pre-releases and additionally treat `0.x` as inherently unstable.
+    Strict,
+}
+
+/// Whether the spec *itself* names a pre-release or a `0.x` version. When it
+/// does, the stability filter is bypassed for that class so pinning
+/// `1.0.0-rc.1` or `0.5` still resolves even under a strict setting.
+fn spec_names_unstable(spec: &UnresolvedVersionSpec) -> (bool, bool) {
+    match spec {
+        UnresolvedVersionSpec::Version(version) => {
+            (!version.pre.is_empty(), version.major == 0)
+        }
+        UnresolvedVersionSpec::Req(req) => (
+            req.comparators.iter().any(|c| !c.pre.is_empty()),
+            req.comparators.iter().any(|c| c.major == 0),
+        ),
+        UnresolvedVersionSpec::ReqAny(reqs) => (
+            reqs.iter()
+                .flat_map(|req| &req.comparators)
+                .any(|c| !c.pre.is_empty()),
+            reqs.iter()
+                .flat_map(|req| &req.comparators)
+                .any(|c| c.major == 0),
+        ),
+        _ => (false, false),
+    }
+}
+
+/// Given a detected [`UnresolvedVersionSpec`], attempt to satisfy it from the
+/// versions already installed on disk and return the highest match. An exact
+/// version spec must match an installed version exactly; a range is converted
+/// to a [`VersionReq`] and the newest matching install is chosen, subject to
+/// the [`Stability`] filter. Returns `None` when the spec can only be resolved
+/// remotely (canary, aliases) or when nothing installed satisfies it, so the
+/// caller falls through to remote resolution.
+fn resolve_from_installed_versions(
+    spec: &UnresolvedVersionSpec,
+    installed: &[Version],
+    stability: Stability,
+) -> Option<UnresolvedVersionSpec> {
+    let (allow_pre, allow_zero) = spec_names_unstable(spec);
+
+    let is_stable_enough = |version: &Version| {
+        if !allow_pre && !version.pre.is_empty() && stability != Stability::All {
+            return false;
+        }
+
+        if !allow_zero && version.major == 0 && stability == Stability::Strict {
+            return false;
+        }
+
+        true
+    };
+
+    let highest_matching = |req: &VersionReq| {
+        installed
+            .iter()
+            .filter(|version| is_stable_enough(version) && req.matches(version))
+            .max()
+            .map(|version| UnresolvedVersionSpec::Version(version.to_owned()))
+    };
+
+    match spec {
+        UnresolvedVersionSpec::Version(version) => installed
+            .iter()
+            .find(|candidate| *candidate == version)
+            .map(|version| UnresolvedVersionSpec::Version(version.to_owned())),
+        UnresolvedVersionSpec::Req(req) => highest_matching(req),
+        UnresolvedVersionSpec::ReqAny(reqs) => installed
+            .iter()
+            .filter(|version| {
+                is_stable_enough(version) && reqs.iter().any(|req| req.matches(version))
+            })
+            .max()
+            .map(|version| UnresolvedVersionSpec::Version(version.to_owned())),
+        // Canary and aliases carry no version requirement we can match locally.
+        _ => None,
+    }
+}
+
 #[instrument(name = "first_available", skip_all)]
 pub async fn detect_version_first_available(
     tool: &Tool,
     config_manager: &ProtoConfigManager,
-) -> miette::Result<Option<UnresolvedVersionSpec>> {
+) -> miette::Result<Option<DetectedVersion>> {
     for file in &config_manager.files {
         if let Some(versions) = &file.config.versions {
             if let Some(version) = versions.get(tool.id.as_str()) {
@@ -28,7 +322,10 @@ pub async fn detect_version_first_available(
 
                 set_detected_env_var(&file.path);
 
-                return Ok(Some(version.to_owned()));
+                return Ok(Some(DetectedVersion::new(
+                    version.to_owned(),
+                    DetectSource::ConfigFile(file.path.to_owned()),
+                )));
             }
         }
 
@@ -44,7 +341,10 @@ pub async fn detect_version_first_available(
 
             set_detected_env_var(&file);
 
-            return Ok(Some(version));
+            return Ok(Some(DetectedVersion::new(
+                version,
+                DetectSource::Ecosystem(file),
+            )));
         }
     }
 
@@ -55,7 +355,7 @@ pub async fn detect_version_first_available(
 pub async fn detect_version_only_prototools(
     tool: &Tool,
     config_manager: &ProtoConfigManager,
-) -> miette::Result<Option<UnresolvedVersionSpec>> {
+) -> miette::Result<Option<DetectedVersion>> {
     for file in &config_manager.files {
         if let Some(versions) = &file.config.versions {
             if let Some(version) = versions.get(tool.id.as_str()) {
@@ -68,7 +368,10 @@ pub async fn detect_version_only_prototools(
 
                 set_detected_env_var(&file.path);
 
-                return Ok(Some(version.to_owned()));
+                return Ok(Some(DetectedVersion::new(
+                    version.to_owned(),
+                    DetectSource::ConfigFile(file.path.to_owned()),
+                )));
             }
         }
     }
@@ -80,10 +383,10 @@ pub async fn detect_version_only_prototools(
 pub async fn detect_version_prefer_prototools(
     tool: &Tool,
     config_manager: &ProtoConfigManager,
-) -> miette::Result<Option<UnresolvedVersionSpec>> {
+) -> miette::Result<Option<DetectedVersion>> {
     // Check config files first
-    if let Some(version) = detect_version_only_prototools(tool, config_manager).await? {
-        return Ok(Some(version));
+    if let Some(detected) = detect_version_only_prototools(tool, config_manager).await? {
+        return Ok(Some(detected));
     }
 
     // Then check the ecosystem
@@ -100,7 +403,10 @@ pub async fn detect_version_prefer_prototools(
 
             set_detected_env_var(&file);
 
-            return Ok(Some(version));
+            return Ok(Some(DetectedVersion::new(
+                version,
+                DetectSource::Ecosystem(file),
+            )));
         }
     }
 
@@ -112,6 +418,19 @@ pub async fn detect_version(
     tool: &Tool,
     forced_version: Option<UnresolvedVersionSpec>,
 ) -> miette::Result<UnresolvedVersionSpec> {
+    Ok(detect_version_with_source(tool, forced_version)
+        .await?
+        .spec)
+}
+
+/// Like [`detect_version`], but also returns the [`DetectSource`] the version
+/// requirement was resolved from. Commands such as `proto status` use this to
+/// explain, per tool, which file or environment variable supplied the version.
+#[instrument(skip_all)]
+pub async fn detect_version_with_source(
+    tool: &Tool,
+    forced_version: Option<UnresolvedVersionSpec>,
+) -> miette::Result<DetectedVersion> {
     if let Some(candidate) = forced_version {
         debug!(
             tool = tool.id.as_str(),
@@ -119,7 +438,33 @@ pub async fn detect_version(
             "Using explicit version passed on the command line",
         );
 
-        return Ok(candidate);
+        return Ok(DetectedVersion::new(candidate, DetectSource::CommandLine));
+    }
+
+    // A global session override pins every tool to the same spec, regardless of
+    // per-tool config. An explicit command-line version still wins over it, but
+    // it takes precedence over the per-tool env var and config traversal.
+    if let Ok(global_version) = env::var(GLOBAL_VERSION_ENV_VAR) {
+        if !global_version.is_empty() {
+            debug!(
+                tool = tool.id.as_str(),
+                env_var = GLOBAL_VERSION_ENV_VAR,
+                version = global_version,
+                "Using global session version override",
+            );
+
+            let spec = UnresolvedVersionSpec::parse(&global_version).map_err(|error| {
+                ProtoError::Semver {
+                    version: global_version,
+                    error: Box::new(error),
+                }
+            })?;
+
+            return Ok(DetectedVersion::new(
+                spec,
+                DetectSource::EnvVar(GLOBAL_VERSION_ENV_VAR.to_owned()),
+            ));
+        }
     }
 
     // Env var takes highest priority
@@ -134,14 +479,14 @@ pub async fn detect_version(
                 "Detected version from environment variable",
             );
 
-            return Ok(
-                UnresolvedVersionSpec::parse(&session_version).map_err(|error| {
-                    ProtoError::Semver {
-                        version: session_version,
-                        error: Box::new(error),
-                    }
-                })?,
-            );
+            let spec = UnresolvedVersionSpec::parse(&session_version).map_err(|error| {
+                ProtoError::Semver {
+                    version: session_version,
+                    error: Box::new(error),
+                }
+            })?;
+
+            return Ok(DetectedVersion::new(spec, DetectSource::EnvVar(env_var)));
         }
     }
 
@@ -167,8 +512,27 @@ pub async fn detect_version(
         }
     };
 
-    if let Some(version) = detected_version {
-        return Ok(version);
+    if let Some(mut detected) = detected_version {
+        // Prefer an already-installed version that satisfies the detected
+        // requirement so offline and repeat invocations avoid the registry.
+        if resolve_from_installed_enabled() {
+            if let Some(resolved) = resolve_from_installed_versions(
+                &detected.spec,
+                &load_installed_versions(tool),
+                detect_stability(),
+            ) {
+                debug!(
+                    tool = tool.id.as_str(),
+                    spec = detected.spec.to_string(),
+                    version = resolved.to_string(),
+                    "Satisfied detected version requirement from an installed version",
+                );
+
+                detected.spec = resolved;
+            }
+        }
+
+        return Ok(detected);
     }
 
     // We didn't find anything!
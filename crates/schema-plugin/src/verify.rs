@@ -1,10 +1,48 @@
 use crate::SchemaPlugin;
-use proto_core::{async_trait, color, get_sha256_hash_of_file, ProtoError, Verifiable};
+use proto_core::{
+    async_trait, color, get_sha1_hash_of_file, get_sha256_hash_of_file, get_sha512_hash_of_file,
+    ProtoError, Verifiable,
+};
+use minisign_verify::{PublicKey, Signature};
 use starbase_utils::fs;
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use tracing::debug;
 
+/// A checksum digest algorithm supported by schema-based checksum files. The
+/// algorithm is normally inferred from the length of the leading hex token on
+/// each line, but can be forced via the `checksum_algorithm` install hint when
+/// a file mixes formats ambiguously.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+enum ChecksumAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl ChecksumAlgorithm {
+    /// Infer the algorithm from the length of a leading hex checksum token,
+    /// returning `None` for lengths that don't map to a supported algorithm.
+    fn from_hex_len(len: usize) -> Option<Self> {
+        match len {
+            40 => Some(Self::Sha1),
+            64 => Some(Self::Sha256),
+            128 => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    /// Compute this algorithm's digest of the downloaded file.
+    fn hash_file(&self, file: &Path) -> Result<String, ProtoError> {
+        match self {
+            Self::Sha1 => get_sha1_hash_of_file(file),
+            Self::Sha256 => get_sha256_hash_of_file(file),
+            Self::Sha512 => get_sha512_hash_of_file(file),
+        }
+    }
+}
+
 #[async_trait]
 impl Verifiable<'_> for SchemaPlugin {
     fn get_checksum_path(&self) -> Result<PathBuf, ProtoError> {
@@ -26,24 +64,76 @@ impl Verifiable<'_> for SchemaPlugin {
         &self,
         checksum_file: &Path,
         download_file: &Path,
+    ) -> Result<bool, ProtoError> {
+        // The checksum file must match.
+        let checksum_ok = self.match_checksum(checksum_file, download_file)?;
+
+        // When a detached signature is also configured, it must pass too — both
+        // are required, so neither a good checksum nor a good signature alone is
+        // sufficient.
+        if self.schema.install.signature_url.is_some() || self.schema.install.public_key.is_some() {
+            let signature_file = self.get_signature_path()?;
+            let signature_ok = self.verify_signature(&signature_file, download_file).await?;
+
+            return Ok(checksum_ok && signature_ok);
+        }
+
+        Ok(checksum_ok)
+    }
+}
+
+impl SchemaPlugin {
+    /// Compare the downloaded file against every line of the checksum file,
+    /// auto-detecting the digest algorithm by hex length (or using the schema's
+    /// forced hint). Returns `Ok(true)` on the first match and fails with
+    /// [`ProtoError::VerifyInvalidChecksum`] once all lines are exhausted.
+    fn match_checksum(
+        &self,
+        checksum_file: &Path,
+        download_file: &Path,
     ) -> Result<bool, ProtoError> {
         debug!(
-            "Verifiying checksum of downloaded file {} using {}",
+            "Verifying checksum of downloaded file {} using {}",
             color::path(download_file),
             color::path(checksum_file),
         );
 
-        let checksum = get_sha256_hash_of_file(download_file)?;
+        // A forced algorithm from the schema overrides length-based detection.
+        let forced_algorithm = match self.schema.install.checksum_algorithm.as_deref() {
+            Some("sha1") => Some(ChecksumAlgorithm::Sha1),
+            Some("sha256") => Some(ChecksumAlgorithm::Sha256),
+            Some("sha512") => Some(ChecksumAlgorithm::Sha512),
+            _ => None,
+        };
 
+        // Each algorithm's digest is computed at most once, the first time a
+        // line using that algorithm is encountered.
+        let mut digests: HashMap<ChecksumAlgorithm, String> = HashMap::new();
         let file = fs::open_file(checksum_file)?;
         let file_name = fs::file_name(download_file);
 
         for line in BufReader::new(file).lines().flatten() {
+            let token = line.split_whitespace().next().unwrap_or_default();
+
+            let Some(algorithm) =
+                forced_algorithm.or_else(|| ChecksumAlgorithm::from_hex_len(token.len()))
+            else {
+                continue;
+            };
+
+            // Hash the download lazily, at most once per algorithm.
+            if !digests.contains_key(&algorithm) {
+                let computed = algorithm.hash_file(download_file)?;
+                digests.insert(algorithm, computed);
+            }
+
+            let checksum = &digests[&algorithm];
+
             if
             // <checksum>  <file>
-            line.starts_with(&checksum) && line.ends_with(&file_name) ||
+            line.starts_with(checksum.as_str()) && line.ends_with(&file_name) ||
             // <checksum>
-            line == checksum
+            line == *checksum
             {
                 debug!("Successfully verified, checksum matches");
 
@@ -56,4 +146,84 @@ impl Verifiable<'_> for SchemaPlugin {
             checksum_file.to_path_buf(),
         ))
     }
-}
\ No newline at end of file
+}
+
+impl SchemaPlugin {
+    /// Name of the detached signature file, derived from the checksum file with
+    /// a `.minisig` suffix (the minisign convention).
+    fn get_signature_file(&self) -> Result<String, ProtoError> {
+        Ok(format!("{}.minisig", self.get_checksum_file()?))
+    }
+
+    /// On-disk location the signature is downloaded to, alongside the checksum.
+    pub fn get_signature_path(&self) -> Result<PathBuf, ProtoError> {
+        Ok(self.temp_dir.join(self.get_signature_file()?))
+    }
+
+    /// Build the detached-signature URL, interpolating the same tokens as
+    /// [`get_checksum_url`](Verifiable::get_checksum_url). Returns `None` when
+    /// the schema configures no `signature_url`.
+    pub fn get_signature_url(&self) -> Result<Option<String>, ProtoError> {
+        if let Some(url) = &self.schema.install.signature_url {
+            return Ok(Some(
+                self.interpolate_tokens(url)
+                    .replace("{checksum_file}", &self.get_checksum_file()?),
+            ));
+        }
+
+        Ok(None)
+    }
+
+    /// Verify the downloaded artifact against a detached minisign signature
+    /// using the schema's embedded trusted `public_key`. A configured signature
+    /// with no key is a misconfiguration and fails rather than passing. When a
+    /// checksum is also configured, the caller requires both to pass.
+    pub async fn verify_signature(
+        &self,
+        signature_file: &Path,
+        download_file: &Path,
+    ) -> Result<bool, ProtoError> {
+        debug!(
+            "Verifying signature of downloaded file {} using {}",
+            color::path(download_file),
+            color::path(signature_file),
+        );
+
+        // A signature configured without a trusted key cannot be verified, so
+        // treat it as a failure instead of silently reporting success.
+        let Some(public_key) = &self.schema.install.public_key else {
+            return Err(ProtoError::VerifyInvalidChecksum(
+                download_file.to_path_buf(),
+                signature_file.to_path_buf(),
+            ));
+        };
+
+        let public_key = PublicKey::from_base64(public_key).map_err(|_| {
+            ProtoError::VerifyInvalidChecksum(
+                download_file.to_path_buf(),
+                signature_file.to_path_buf(),
+            )
+        })?;
+
+        let signature = Signature::decode(&fs::read_file(signature_file)?).map_err(|_| {
+            ProtoError::VerifyInvalidChecksum(
+                download_file.to_path_buf(),
+                signature_file.to_path_buf(),
+            )
+        })?;
+
+        if public_key
+            .verify(&fs::read_file_bytes(download_file)?, &signature, false)
+            .is_ok()
+        {
+            debug!("Successfully verified, signature matches");
+
+            return Ok(true);
+        }
+
+        Err(ProtoError::VerifyInvalidChecksum(
+            download_file.to_path_buf(),
+            signature_file.to_path_buf(),
+        ))
+    }
+}